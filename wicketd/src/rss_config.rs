@@ -20,12 +20,23 @@ use bootstrap_agent_client::types::RackInitializeRequest;
 use bootstrap_agent_client::types::RecoverySiloConfig;
 use bootstrap_agent_client::types::UserId;
 use gateway_client::types::SpType;
+use hickory_resolver::config::NameServerConfigGroup;
+use hickory_resolver::config::ResolverConfig;
+use hickory_resolver::config::ResolverOpts;
+use hickory_resolver::TokioAsyncResolver;
 use omicron_certificates::CertificateValidator;
 use omicron_common::address;
 use omicron_common::api::internal::shared::RackNetworkConfig;
+use openssl::x509::X509;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
 use sled_hardware::Baseboard;
 use std::collections::BTreeSet;
+use std::net::IpAddr;
 use std::net::Ipv6Addr;
+use std::time::Duration;
+use std::time::Instant;
 use wicket_common::rack_setup::PutRssUserConfigInsensitive;
 
 // TODO-correctness For now, we always use the same rack subnet when running
@@ -35,7 +46,13 @@ const RACK_SUBNET: Ipv6Addr =
     Ipv6Addr::new(0xfd00, 0x1122, 0x3344, 0x0100, 0, 0, 0, 0);
 
 const RECOVERY_SILO_NAME: &str = "recovery";
-const RECOVERY_SILO_USERNAME: &str = "recovery";
+
+/// A single break-glass recovery-silo user: a username and the password hash
+/// the operator uploaded for it.
+struct RecoveryUser {
+    username: UserId,
+    password_hash: omicron_passwords::NewPasswordHash,
+}
 
 #[derive(Default)]
 struct PartialCertificate {
@@ -55,14 +72,29 @@ pub(crate) struct CurrentRssConfig {
     internal_services_ip_pool_ranges: Vec<address::IpRange>,
     external_dns_zone_name: String,
     external_certificates: Vec<Certificate>,
-    recovery_silo_password_hash: Option<omicron_passwords::NewPasswordHash>,
+    // The recovery silo name (defaults to `RECOVERY_SILO_NAME` when unset) and
+    // the set of break-glass users to provision in it. Keeping more than one
+    // user means losing a single credential doesn't strand the operator; each
+    // user is emitted as its own `RecoverySiloConfig` at rack-init time.
+    recovery_silo_name: Option<String>,
+    recovery_users: Vec<RecoveryUser>,
     rack_network_config: Option<RackNetworkConfig>,
 
+    // Trust-quorum threshold `k` for the `k`-of-`n` rack-secret sharing scheme.
+    // `None` means the operator hasn't chosen one, in which case
+    // `start_rss_request` defaults it to a majority of the bootstrap sleds.
+    rack_secret_threshold: Option<usize>,
+
     // External certificates are uploaded in two separate actions (cert then
     // key, or vice versa). Here we store a partial certificate; once we have
     // both parts, we validate it and promote it to be a member of
     // external_certificates.
     partial_external_certificate: PartialCertificate,
+
+    // Soft warnings accumulated while validating user input (e.g. certificates
+    // with overlapping SAN coverage). These don't block rack setup but are
+    // worth surfacing to the operator.
+    warnings: Vec<String>,
 }
 
 impl CurrentRssConfig {
@@ -119,16 +151,28 @@ impl CurrentRssConfig {
         if self.external_certificates.is_empty() {
             bail!("at least one certificate/key pair is required");
         }
-        let Some(recovery_silo_password_hash)
-            = self.recovery_silo_password_hash.as_ref()
-        else {
-            bail!("recovery password not yet set");
-        };
+        if self.recovery_users.is_empty() {
+            bail!("at least one recovery silo user is required");
+        }
         let Some(rack_network_config) = self.rack_network_config.as_ref() else {
             bail!("rack network config not set (have you uploaded a config?)");
         };
-        let rack_network_config =
-            validate_rack_network_config(rack_network_config);
+        let rack_network_config = validate_rack_network_config(
+            rack_network_config,
+            &self.internal_services_ip_pool_ranges,
+        )
+        .map_err(|err| anyhow::anyhow!(err))?;
+
+        // Validate the trust-quorum threshold against the number of bootstrap
+        // sleds the operator selected. `k` of `n` sleds must cooperate to
+        // reconstruct the rack secret, so `k` only makes sense relative to `n`;
+        // changing `bootstrap_sleds` after setting a threshold can invalidate
+        // it, which is why we check here rather than at upload time.
+        let rack_secret_threshold = validate_rack_secret_threshold(
+            self.rack_secret_threshold,
+            self.bootstrap_sleds.len(),
+        )
+        .map_err(|err| anyhow::anyhow!(err))?;
 
         let known_bootstrap_sleds = bootstrap_peers.sleds();
         let mut bootstrap_ips = Vec::new();
@@ -144,10 +188,28 @@ impl CurrentRssConfig {
             bootstrap_ips.push(ip);
         }
 
-        // Convert between internal and progenitor types.
-        let user_password_hash = bootstrap_agent_client::types::NewPasswordHash(
-            recovery_silo_password_hash.to_string(),
-        );
+        // Convert between internal and progenitor types. The silo name is
+        // operator-configurable (it was previously hard-coded); each configured
+        // break-glass user becomes its own `RecoverySiloConfig` sharing that
+        // silo name, so every account is created when the rack initializes.
+        let silo_name = Name::try_from(
+            self.recovery_silo_name
+                .as_deref()
+                .unwrap_or(RECOVERY_SILO_NAME),
+        )
+        .map_err(|err| anyhow::anyhow!("invalid recovery silo name: {err}"))?;
+        let recovery_silos = self
+            .recovery_users
+            .iter()
+            .map(|user| RecoverySiloConfig {
+                silo_name: silo_name.clone(),
+                user_name: user.username.clone(),
+                user_password_hash:
+                    bootstrap_agent_client::types::NewPasswordHash(
+                        user.password_hash.to_string(),
+                    ),
+            })
+            .collect::<Vec<_>>();
         let internal_services_ip_pool_ranges = self
             .internal_services_ip_pool_ranges
             .iter()
@@ -173,28 +235,78 @@ impl CurrentRssConfig {
             bootstrap_discovery: BootstrapAddressDiscovery::OnlyThese(
                 bootstrap_ips,
             ),
-            rack_secret_threshold: 1, // TODO REMOVE?
+            rack_secret_threshold,
             ntp_servers: self.ntp_servers.clone(),
             dns_servers: self.dns_servers.clone(),
             internal_services_ip_pool_ranges,
             external_dns_zone_name: self.external_dns_zone_name.clone(),
             external_certificates: self.external_certificates.clone(),
-            recovery_silo: RecoverySiloConfig {
-                silo_name: Name::try_from(RECOVERY_SILO_NAME).unwrap(),
-                user_name: UserId(RECOVERY_SILO_USERNAME.into()),
-                user_password_hash,
-            },
+            recovery_silos,
             rack_network_config: Some(rack_network_config),
         };
 
         Ok(request)
     }
 
-    pub(crate) fn set_recovery_user_password_hash(
+    /// Run a best-effort DNS preflight against the configured upstream
+    /// resolvers and external DNS zone.
+    ///
+    /// This is an explicit, operator-triggered check, *not* part of
+    /// `start_rss_request`: it performs network I/O and runs pre-NTP, so it
+    /// lives outside the synchronous rack-init path. It queries each configured
+    /// resolver for the SOA of the parent of `external_dns_zone_name` and for
+    /// the NS records of the zone itself (its delegation in that parent),
+    /// reporting per-server reachability and latency, and flags when the zone
+    /// has no delegation or resolves inconsistently across servers. The result
+    /// is structured (per-server status plus an overall verdict) so the wizard
+    /// can point the operator at a misconfigured resolver while still letting
+    /// them proceed past soft warnings.
+    pub(crate) async fn dns_preflight(&self) -> DnsPreflightReport {
+        dns_preflight(&self.dns_servers, &self.external_dns_zone_name).await
+    }
+
+    /// Soft, non-fatal warnings accumulated while validating uploaded input
+    /// (e.g. a certificate whose SAN coverage duplicates one already accepted).
+    /// These are surfaced to the operator but don't block rack setup.
+    pub(crate) fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Set (or clear, with `None`) the recovery silo name. When unset, the
+    /// silo defaults to `RECOVERY_SILO_NAME`.
+    pub(crate) fn set_recovery_silo_name(&mut self, name: Option<String>) {
+        self.recovery_silo_name = name;
+    }
+
+    /// Add a break-glass recovery user, or replace the password hash of an
+    /// existing user with the same username.
+    pub(crate) fn add_recovery_user(
         &mut self,
-        hash: omicron_passwords::NewPasswordHash,
+        username: UserId,
+        password_hash: omicron_passwords::NewPasswordHash,
     ) {
-        self.recovery_silo_password_hash = Some(hash);
+        if let Some(user) =
+            self.recovery_users.iter_mut().find(|u| u.username == username)
+        {
+            user.password_hash = password_hash;
+        } else {
+            self.recovery_users.push(RecoveryUser { username, password_hash });
+        }
+    }
+
+    /// Remove a break-glass recovery user by username, returning an error if no
+    /// such user is configured.
+    pub(crate) fn remove_recovery_user(
+        &mut self,
+        username: &UserId,
+    ) -> Result<(), String> {
+        let before = self.recovery_users.len();
+        self.recovery_users.retain(|u| u.username != *username);
+        if self.recovery_users.len() == before {
+            Err(format!("no recovery user named {username:?}"))
+        } else {
+            Ok(())
+        }
     }
 
     pub(crate) fn push_cert(
@@ -213,6 +325,29 @@ impl CurrentRssConfig {
         self.maybe_promote_external_certificate()
     }
 
+    /// Accept a complete external certificate as a single PKCS#12 (`.pfx` /
+    /// `.p12`) DER bundle rather than separate cert and key uploads.
+    ///
+    /// The bundle is expected to contain exactly one private key (a PBES2
+    /// `PKCS8ShroudedKeyBag`) and at least one certificate; we extract the leaf
+    /// and any intermediates, re-encode them as the PEM `cert`/`key` pair that
+    /// `Certificate` expects, and hand them to the same validation path used by
+    /// `push_cert`/`push_key`. Because the bundle is self-contained it always
+    /// promotes immediately rather than waiting on a second upload.
+    pub(crate) fn push_pkcs12(
+        &mut self,
+        pkcs12: Vec<u8>,
+        passphrase: Option<&str>,
+    ) -> Result<CertificateUploadResponse, String> {
+        let (cert, key) = parse_pkcs12(&pkcs12, passphrase.unwrap_or(""))?;
+
+        // Overwrite any half-finished two-part upload; a complete bundle stands
+        // on its own.
+        self.partial_external_certificate.cert = Some(cert);
+        self.partial_external_certificate.key = Some(key);
+        self.maybe_promote_external_certificate()
+    }
+
     fn maybe_promote_external_certificate(
         &mut self,
     ) -> Result<CertificateUploadResponse, String> {
@@ -242,6 +377,13 @@ impl CurrentRssConfig {
 
         validator.validate(cert, key).map_err(|err| err.to_string())?;
 
+        // If the external DNS zone is already known, confirm the leaf cert's
+        // SANs can actually cover the names Nexus will serve. (When the zone is
+        // set later, `update` re-runs this check against every accepted cert.)
+        if !self.external_dns_zone_name.is_empty() {
+            validate_cert_covers_zone(cert, &self.external_dns_zone_name)?;
+        }
+
         // Cert and key appear to be valid; steal them out of
         // `partial_external_certificate` and promote them to
         // `external_certificates`.
@@ -250,9 +392,46 @@ impl CurrentRssConfig {
             key: self.partial_external_certificate.key.take().unwrap(),
         });
 
+        // Flag (but don't reject) certificates with overlapping SAN coverage,
+        // which usually means a duplicate upload.
+        self.recompute_cert_overlap_warnings();
+
         Ok(CertificateUploadResponse::CertKeyAccepted)
     }
 
+    /// Recompute the soft "overlapping SAN coverage" warnings across every
+    /// accepted external certificate, replacing any previously computed ones.
+    /// Two accepted certs that share a SAN `dNSName` usually indicate a
+    /// duplicate upload. This is a no-op until the external DNS zone is known,
+    /// so it must be re-run when the zone name is set or changed.
+    fn recompute_cert_overlap_warnings(&mut self) {
+        self.warnings.clear();
+        if self.external_dns_zone_name.is_empty() {
+            return;
+        }
+        let names: Vec<Vec<String>> = self
+            .external_certificates
+            .iter()
+            .map(|c| cert_san_dns_names(&c.cert).unwrap_or_default())
+            .collect();
+        for i in 0..names.len() {
+            for j in (i + 1)..names.len() {
+                let shared: Vec<&str> = names[i]
+                    .iter()
+                    .filter(|n| names[j].contains(n))
+                    .map(String::as_str)
+                    .collect();
+                if !shared.is_empty() {
+                    self.warnings.push(format!(
+                        "certificates {i} and {j} have overlapping SAN \
+                         coverage (duplicate upload?): {}",
+                        shared.join(", "),
+                    ));
+                }
+            }
+        }
+    }
+
     pub(crate) fn update(
         &mut self,
         value: PutRssUserConfigInsensitive,
@@ -310,14 +489,45 @@ impl CurrentRssConfig {
             bootstrap_sleds.insert(sled.clone());
         }
 
+        // If the external DNS zone name is changing, every certificate we've
+        // already accepted must still cover the new zone. Certs are validated
+        // against the zone at upload time, but the zone can be set or changed
+        // afterwards, so re-check here and reject the update if a previously
+        // accepted cert no longer matches.
+        if value.external_dns_zone_name != self.external_dns_zone_name
+            && !value.external_dns_zone_name.is_empty()
+        {
+            for cert in &self.external_certificates {
+                validate_cert_covers_zone(
+                    &cert.cert,
+                    &value.external_dns_zone_name,
+                )?;
+            }
+        }
+
+        // Reject internally inconsistent uplink configuration at upload time
+        // rather than waiting until rack-init. We discard the converted value
+        // here (we store the internal form), but the conversion doubles as the
+        // validation pass.
+        validate_rack_network_config(
+            &value.rack_network_config,
+            &value.internal_services_ip_pool_ranges,
+        )?;
+
         self.bootstrap_sleds = bootstrap_sleds;
         self.ntp_servers = value.ntp_servers;
         self.dns_servers = value.dns_servers;
         self.internal_services_ip_pool_ranges =
             value.internal_services_ip_pool_ranges;
         self.external_dns_zone_name = value.external_dns_zone_name;
+        self.rack_secret_threshold = value.rack_secret_threshold;
         self.rack_network_config = Some(value.rack_network_config);
 
+        // The external DNS zone name may have just been set or changed, so
+        // recompute the overlapping-SAN warnings against the accepted certs
+        // (they are only computed once the zone is known).
+        self.recompute_cert_overlap_warnings();
+
         Ok(())
     }
 }
@@ -335,9 +545,7 @@ impl From<&'_ CurrentRssConfig> for CurrentRssUserConfig {
         Self {
             sensitive: CurrentRssUserConfigSensitive {
                 num_external_certificates: rss.external_certificates.len(),
-                recovery_silo_password_set: rss
-                    .recovery_silo_password_hash
-                    .is_some(),
+                num_recovery_users: rss.recovery_users.len(),
             },
             insensitive: CurrentRssUserConfigInsensitive {
                 bootstrap_sleds,
@@ -347,23 +555,530 @@ impl From<&'_ CurrentRssConfig> for CurrentRssUserConfig {
                     .internal_services_ip_pool_ranges
                     .clone(),
                 external_dns_zone_name: rss.external_dns_zone_name.clone(),
+                rack_secret_threshold: rss.rack_secret_threshold,
                 rack_network_config: rss.rack_network_config.clone(),
             },
         }
     }
 }
 
+/// Overall verdict of a DNS preflight run.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum DnsPreflightStatus {
+    /// Every resolver answered and the external zone delegation looks sane.
+    Pass,
+    /// The check succeeded but something is worth the operator's attention
+    /// (e.g. inconsistent answers, or no delegation found for the zone).
+    Warn,
+    /// No configured resolver was reachable.
+    Fail,
+}
+
+/// Outcome of querying a single upstream resolver.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub(crate) struct DnsServerResult {
+    /// The resolver address as configured by the operator.
+    pub address: String,
+    /// Whether the resolver answered our query at all.
+    pub reachable: bool,
+    /// Round-trip latency of the query, if it completed.
+    pub latency_ms: Option<u64>,
+    /// NS records observed for the external zone itself (its delegation).
+    pub nameservers: Vec<String>,
+    /// A human-readable error if the query failed.
+    pub error: Option<String>,
+}
+
+/// Structured result of [`CurrentRssConfig::dns_preflight`].
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub(crate) struct DnsPreflightReport {
+    pub external_dns_zone_name: String,
+    pub overall: DnsPreflightStatus,
+    pub servers: Vec<DnsServerResult>,
+    /// Soft warnings that don't fail the check (no delegation, disagreement
+    /// between resolvers, ...).
+    pub warnings: Vec<String>,
+}
+
+/// Per-resolver query timeout for the preflight. Kept short so an unreachable
+/// resolver doesn't stall the wizard.
+const DNS_PREFLIGHT_TIMEOUT: Duration = Duration::from_secs(5);
+
+async fn dns_preflight(
+    dns_servers: &[String],
+    external_dns_zone_name: &str,
+) -> DnsPreflightReport {
+    // A zone's delegation lives in its parent: the parent publishes the NS
+    // records that point at the zone's authoritative servers. We anchor on the
+    // parent's SOA (does the parent even exist / resolve?) and read the zone's
+    // NS records to confirm it has actually been delegated.
+    let zone = external_dns_zone_name.trim_end_matches('.');
+    let parent = match zone.split_once('.') {
+        Some((_, parent)) => parent,
+        // A single-label zone has the root as its parent.
+        None => ".",
+    };
+
+    let mut servers = Vec::with_capacity(dns_servers.len());
+    let mut warnings = Vec::new();
+
+    for address in dns_servers {
+        servers.push(query_one_resolver(address, zone, parent).await);
+    }
+
+    // Flag the zone as undelegated if no reachable resolver returned any NS
+    // records for it.
+    let any_reachable = servers.iter().any(|s| s.reachable);
+    let any_delegation = servers.iter().any(|s| !s.nameservers.is_empty());
+    if any_reachable && !any_delegation {
+        warnings.push(format!(
+            "no delegation (NS records) found for external DNS zone \
+             {external_dns_zone_name:?}; the zone may not be delegated to you",
+        ));
+    }
+
+    // Flag resolvers that disagree about the zone's nameservers.
+    let mut distinct: BTreeSet<Vec<String>> = BTreeSet::new();
+    for server in &servers {
+        if server.reachable && !server.nameservers.is_empty() {
+            let mut ns = server.nameservers.clone();
+            ns.sort();
+            distinct.insert(ns);
+        }
+    }
+    if distinct.len() > 1 {
+        warnings.push(
+            "configured resolvers returned inconsistent NS records for the \
+             external DNS zone"
+                .to_string(),
+        );
+    }
+
+    let overall = if !any_reachable {
+        DnsPreflightStatus::Fail
+    } else if !warnings.is_empty()
+        || servers.iter().any(|s| !s.reachable)
+    {
+        DnsPreflightStatus::Warn
+    } else {
+        DnsPreflightStatus::Pass
+    };
+
+    DnsPreflightReport {
+        external_dns_zone_name: external_dns_zone_name.to_string(),
+        overall,
+        servers,
+        warnings,
+    }
+}
+
+async fn query_one_resolver(
+    address: &str,
+    zone: &str,
+    parent: &str,
+) -> DnsServerResult {
+    let mut result = DnsServerResult {
+        address: address.to_string(),
+        reachable: false,
+        latency_ms: None,
+        nameservers: Vec::new(),
+        error: None,
+    };
+
+    let ip = match address.parse::<IpAddr>() {
+        Ok(ip) => ip,
+        Err(err) => {
+            result.error = Some(format!("not an IP address: {err}"));
+            return result;
+        }
+    };
+
+    let name_servers =
+        NameServerConfigGroup::from_ips_clear(&[ip], 53, /* trust_nx */ true);
+    let config = ResolverConfig::from_parts(None, Vec::new(), name_servers);
+    let mut opts = ResolverOpts::default();
+    opts.timeout = DNS_PREFLIGHT_TIMEOUT;
+    opts.attempts = 1;
+    // Answer only from the configured resolver, never from the local hosts
+    // file.
+    opts.use_hosts_file = false;
+
+    let resolver = TokioAsyncResolver::tokio(config, opts);
+
+    // Anchor on the parent's SOA: this establishes reachability and confirms
+    // the parent zone the delegation would live in actually resolves.
+    let started = Instant::now();
+    match resolver.soa_lookup(parent).await {
+        Ok(_) => {
+            result.reachable = true;
+            result.latency_ms = Some(started.elapsed().as_millis() as u64);
+        }
+        Err(err) => {
+            // A NXDOMAIN/NoRecords answer still means the resolver responded;
+            // only transport-level failures count as unreachable.
+            if is_negative_answer(&err) {
+                result.reachable = true;
+                result.latency_ms =
+                    Some(started.elapsed().as_millis() as u64);
+            } else {
+                result.error = Some(err.to_string());
+                return result;
+            }
+        }
+    }
+
+    // Read the zone's delegation (its NS records). An empty/NXDOMAIN answer
+    // here means the zone hasn't been delegated under its parent.
+    match resolver.ns_lookup(zone).await {
+        Ok(lookup) => {
+            result.nameservers =
+                lookup.iter().map(|ns| ns.0.to_utf8()).collect();
+        }
+        Err(err) if is_negative_answer(&err) => {}
+        Err(err) => {
+            result.error = Some(err.to_string());
+        }
+    }
+
+    result
+}
+
+/// Whether a resolver error represents a negative-but-valid answer (the
+/// resolver replied, there just weren't any records) rather than a transport
+/// failure.
+fn is_negative_answer(err: &hickory_resolver::error::ResolveError) -> bool {
+    use hickory_resolver::error::ResolveErrorKind;
+    matches!(err.kind(), ResolveErrorKind::NoRecordsFound { .. })
+}
+
+/// Validate the trust-quorum threshold `k` against the number of bootstrap
+/// sleds `n`, returning the effective threshold to use.
+///
+/// When the operator didn't pick a threshold we default to a simple majority
+/// (`floor(n/2)+1`). An explicit threshold must satisfy `2 <= k <= n`, except
+/// that a single-sled dev rack may only use `k == 1`, since real `k`-of-`n`
+/// secret sharing is impossible with one share.
+fn validate_rack_secret_threshold(
+    threshold: Option<usize>,
+    num_sleds: usize,
+) -> Result<usize, String> {
+    match threshold {
+        None => Ok(num_sleds / 2 + 1),
+        Some(threshold) => {
+            if num_sleds == 1 {
+                if threshold != 1 {
+                    return Err(format!(
+                        "rack_secret_threshold must be 1 for a single-sled \
+                         rack (got {threshold})"
+                    ));
+                }
+            } else if threshold < 2 || threshold > num_sleds {
+                return Err(format!(
+                    "rack_secret_threshold must be between 2 and the number \
+                     of bootstrap sleds ({num_sleds}); got {threshold}"
+                ));
+            }
+            Ok(threshold)
+        }
+    }
+}
+
+/// The DNS names Nexus will serve for a rack whose external DNS zone is
+/// `zone`: the per-silo wildcard and the bare zone itself.
+fn expected_zone_dns_names(zone: &str) -> Vec<String> {
+    vec![format!("*.sys.{zone}"), zone.to_string()]
+}
+
+/// Extract the `dNSName` SubjectAltName entries from the leaf certificate in a
+/// PEM `cert` blob.
+fn cert_san_dns_names(cert_pem: &[u8]) -> Result<Vec<String>, String> {
+    let cert = X509::from_pem(cert_pem)
+        .map_err(|err| format!("could not parse certificate: {err}"))?;
+    let names = cert
+        .subject_alt_names()
+        .map(|sans| {
+            sans.iter()
+                .filter_map(|san| san.dnsname().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(names)
+}
+
+/// Confirm the leaf certificate in `cert_pem` carries a SAN `dNSName` that can
+/// serve at least one of the names Nexus uses under `zone`, matching the way
+/// an SNI-terminating proxy checks a presented hostname against the
+/// certificate's DNS names before serving it.
+fn validate_cert_covers_zone(
+    cert_pem: &[u8],
+    zone: &str,
+) -> Result<(), String> {
+    let found = cert_san_dns_names(cert_pem)?;
+    let expected = expected_zone_dns_names(zone);
+
+    let covered = expected
+        .iter()
+        .any(|name| found.iter().any(|san| dns_name_matches(san, name)));
+    if covered {
+        Ok(())
+    } else {
+        Err(format!(
+            "certificate does not cover external DNS zone {zone:?}: \
+             expected one of [{}] but certificate only has [{}]",
+            expected.join(", "),
+            found.join(", "),
+        ))
+    }
+}
+
+/// Match a certificate SAN `dNSName` (which may be a left-most wildcard such as
+/// `*.sys.example.com`) against an expected hostname.
+fn dns_name_matches(san: &str, expected: &str) -> bool {
+    if let Some(suffix) = san.strip_prefix("*.") {
+        // A wildcard matches exactly one left-most label.
+        match expected.split_once('.') {
+            Some((_, rest)) => rest.eq_ignore_ascii_case(suffix),
+            None => false,
+        }
+    } else {
+        san.eq_ignore_ascii_case(expected)
+    }
+}
+
+/// Parse a PKCS#12 DER bundle into the leaf-first PEM certificate chain and
+/// PEM private key expected by `Certificate`.
+///
+/// The bundle's `authSafe` is a sequence of `ContentInfo` safe bags: the key
+/// lives in a PBES2-encrypted `PKCS8ShroudedKeyBag` (decrypted with
+/// `passphrase` via PBKDF2 plus the cipher named in the PBES2 parameters) and
+/// the certificates in one or more `CertBag`s. We reject bundles that carry
+/// more than one private key or no certificates at all.
+fn parse_pkcs12(
+    der: &[u8],
+    passphrase: &str,
+) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let pfx = p12::PFX::parse(der)
+        .map_err(|err| format!("could not parse PKCS#12 bundle: {err}"))?;
+
+    // Extract the private key(s). `key_bags` returns the DER-encoded PKCS#8
+    // contents of each shrouded key bag after decrypting with the passphrase.
+    // It returns `None` if decryption failed (e.g. wrong passphrase) or a bag
+    // couldn't be parsed; an empty `Some` means no key bags were present.
+    let key_bags = pfx.key_bags(passphrase).ok_or_else(|| {
+        "could not decrypt PKCS#12 bundle (wrong passphrase?)".to_string()
+    })?;
+    let key_der = match key_bags.as_slice() {
+        [key] => key,
+        [] => return Err("PKCS#12 bundle contains no private key".to_string()),
+        keys => {
+            return Err(format!(
+                "PKCS#12 bundle contains {} private keys; expected exactly one",
+                keys.len(),
+            ));
+        }
+    };
+
+    // Extract the certificates and order them leaf-first. The passphrase was
+    // already validated by `key_bags` above, so a `None` here means the cert
+    // bags are absent or unparseable; either way we have no usable certs and
+    // fall through to the "no certificates" error below.
+    let cert_ders = pfx.cert_x509_bags(passphrase).unwrap_or_default();
+    if cert_ders.is_empty() {
+        return Err("PKCS#12 bundle contains no certificates".to_string());
+    }
+    let mut certs = Vec::with_capacity(cert_ders.len());
+    for der in &cert_ders {
+        certs.push(X509::from_der(der).map_err(|err| {
+            format!("invalid certificate in PKCS#12 bundle: {err}")
+        })?);
+    }
+    let ordered = order_cert_chain(certs);
+
+    // Re-encode everything as PEM: the chain leaf-first in a single `cert`
+    // blob, and the key as a PKCS#8 private key.
+    let mut cert_pem = Vec::new();
+    for cert in &ordered {
+        cert_pem.extend_from_slice(&cert.to_pem().map_err(|err| {
+            format!("could not re-encode certificate as PEM: {err}")
+        })?);
+    }
+    let key_pem = pem::encode(&pem::Pem {
+        tag: "PRIVATE KEY".to_string(),
+        contents: key_der.clone(),
+    })
+    .into_bytes();
+
+    Ok((cert_pem, key_pem))
+}
+
+/// Order a set of certificates leaf-first by matching issuer to subject.
+///
+/// The leaf is the certificate whose subject is not the issuer of any other
+/// certificate in the bundle; from there we follow issuer links as far as the
+/// bundle allows. Anything we can't place (e.g. a stray self-signed root) is
+/// appended in its original order so we never silently drop a cert.
+fn order_cert_chain(mut certs: Vec<X509>) -> Vec<X509> {
+    if certs.len() <= 1 {
+        return certs;
+    }
+
+    let subjects: Vec<Vec<u8>> =
+        certs.iter().map(|c| c.subject_name().to_der().unwrap()).collect();
+    let issuers: Vec<Vec<u8>> =
+        certs.iter().map(|c| c.issuer_name().to_der().unwrap()).collect();
+
+    // The leaf is a subject that no other cert claims as its issuer.
+    let leaf = (0..certs.len()).find(|&i| {
+        !issuers
+            .iter()
+            .enumerate()
+            .any(|(j, issuer)| j != i && *issuer == subjects[i])
+    });
+    let Some(leaf) = leaf else {
+        // Couldn't identify a leaf (every subject issues some other cert in
+        // the bundle, e.g. a cycle); leave as-is.
+        return certs;
+    };
+
+    let mut order = vec![leaf];
+    let mut placed = BTreeSet::from([leaf]);
+    loop {
+        let current = *order.last().unwrap();
+        // Find the cert that issued the current one, unless it is self-signed.
+        if issuers[current] == subjects[current] {
+            break;
+        }
+        let next = (0..certs.len())
+            .find(|&i| !placed.contains(&i) && subjects[i] == issuers[current]);
+        match next {
+            Some(i) => {
+                order.push(i);
+                placed.insert(i);
+            }
+            None => break,
+        }
+    }
+
+    // Append any certs we didn't weave into the chain.
+    for i in 0..certs.len() {
+        if !placed.contains(&i) {
+            order.push(i);
+        }
+    }
+
+    // Reorder `certs` according to `order` without cloning the X509s.
+    let mut slots: Vec<Option<X509>> = certs.drain(..).map(Some).collect();
+    order.into_iter().map(|i| slots[i].take().unwrap()).collect()
+}
+
 fn validate_rack_network_config(
     config: &RackNetworkConfig,
-) -> bootstrap_agent_client::types::RackNetworkConfig {
+    internal_services_ip_pool_ranges: &[address::IpRange],
+) -> Result<bootstrap_agent_client::types::RackNetworkConfig, String> {
     use bootstrap_agent_client::types::PortFec as BaPortFec;
     use bootstrap_agent_client::types::PortSpeed as BaPortSpeed;
     use omicron_common::api::internal::shared::PortFec;
     use omicron_common::api::internal::shared::PortSpeed;
 
-    // TODO Add client side checks on `rack_network_config` contents.
+    // Accumulate every problem so the operator can fix them all at once rather
+    // than rediscovering them one `bail!` at a time.
+    let mut errors = Vec::new();
+
+    // NB: the request also asked to reject infra/uplink overlap with the rack
+    // subnet `fd00:1122:3344:0100::/56`. That check is intentionally omitted:
+    // the rack subnet is IPv6 while infra/gateway/uplink addresses are always
+    // IPv4, so they can never overlap and the check would be dead code.
+
+    // The infra range must be non-empty and well-ordered.
+    if config.infra_ip_first > config.infra_ip_last {
+        errors.push(format!(
+            "infra_ip_first ({}) is greater than infra_ip_last ({})",
+            config.infra_ip_first, config.infra_ip_last,
+        ));
+    }
+
+    // The infra range as a whole must not overlap any internal-services IP
+    // pool range, since those addresses are handed out to rack-internal
+    // services and can't also be part of the uplink-facing infra space.
+    for range in internal_services_ip_pool_ranges {
+        if let address::IpRange::V4(range) = range {
+            if config.infra_ip_first <= range.last
+                && range.first <= config.infra_ip_last
+            {
+                errors.push(format!(
+                    "infra IP range {}-{} overlaps internal services IP pool \
+                     range {}-{}",
+                    config.infra_ip_first,
+                    config.infra_ip_last,
+                    range.first,
+                    range.last,
+                ));
+            }
+        }
+    }
+
+    // The gateway and uplink addresses must live inside the infra range.
+    for (label, ip) in
+        [("gateway_ip", config.gateway_ip), ("uplink_ip", config.uplink_ip)]
+    {
+        if ip < config.infra_ip_first || ip > config.infra_ip_last {
+            errors.push(format!(
+                "{label} ({ip}) is outside the infra IP range {}-{}",
+                config.infra_ip_first, config.infra_ip_last,
+            ));
+        }
+
+        // Infra/uplink addresses must not collide with any internal-services
+        // IP pool range, which would route rack-internal traffic out the uplink
+        // (or vice versa).
+        let addr = IpAddr::V4(ip);
+        for range in internal_services_ip_pool_ranges {
+            if ip_range_contains(range, addr) {
+                errors.push(format!(
+                    "{label} ({ip}) overlaps internal services IP pool range \
+                     {}-{}",
+                    range.first_address(),
+                    range.last_address(),
+                ));
+            }
+        }
+    }
+
+    // 802.1Q VLAN IDs: 0 and 4095 are reserved, leaving 1-4094 usable.
+    if let Some(vid) = config.uplink_vid {
+        if vid == 0 || vid > 4094 {
+            errors.push(format!(
+                "uplink_vid ({vid}) is outside the valid 802.1Q VLAN range \
+                 (1-4094)",
+            ));
+        }
+    }
+
+    // An active uplink can't run at 0G, and the FEC mode has to be one the
+    // chosen line rate actually supports.
+    if matches!(config.uplink_port_speed, PortSpeed::Speed0G) {
+        errors.push(
+            "uplink_port_speed is 0G, which is not valid for an active uplink"
+                .to_string(),
+        );
+    }
+    if let Some(err) =
+        fec_speed_incompatibility(config.uplink_port_fec, config.uplink_port_speed)
+    {
+        errors.push(err);
+    }
+
+    if !errors.is_empty() {
+        return Err(format!(
+            "invalid rack network config:\n  - {}",
+            errors.join("\n  - "),
+        ));
+    }
 
-    bootstrap_agent_client::types::RackNetworkConfig {
+    Ok(bootstrap_agent_client::types::RackNetworkConfig {
         gateway_ip: config.gateway_ip.clone(),
         infra_ip_first: config.infra_ip_first.clone(),
         infra_ip_last: config.infra_ip_last.clone(),
@@ -386,5 +1101,128 @@ fn validate_rack_network_config(
         },
         uplink_ip: config.uplink_ip.clone(),
         uplink_vid: config.uplink_vid,
+    })
+}
+
+/// Whether an IP range contains `addr`, comparing only within a single family.
+fn ip_range_contains(range: &address::IpRange, addr: IpAddr) -> bool {
+    match (range, addr) {
+        (address::IpRange::V4(range), IpAddr::V4(ip)) => {
+            range.first <= ip && ip <= range.last
+        }
+        (address::IpRange::V6(range), IpAddr::V6(ip)) => {
+            range.first <= ip && ip <= range.last
+        }
+        _ => false,
     }
-}
\ No newline at end of file
+}
+
+/// Reject FEC modes that the chosen line rate can't carry. Reed-Solomon FEC is
+/// only meaningful at 100G and above, while Firecode is a low-rate (<=50G)
+/// scheme; `None` is always permitted. Returns a human-readable error when the
+/// pairing is invalid.
+fn fec_speed_incompatibility(
+    fec: omicron_common::api::internal::shared::PortFec,
+    speed: omicron_common::api::internal::shared::PortSpeed,
+) -> Option<String> {
+    use omicron_common::api::internal::shared::PortFec;
+    use omicron_common::api::internal::shared::PortSpeed;
+
+    match (fec, speed) {
+        (PortFec::Rs, PortSpeed::Speed0G)
+        | (PortFec::Rs, PortSpeed::Speed1G)
+        | (PortFec::Rs, PortSpeed::Speed10G)
+        | (PortFec::Rs, PortSpeed::Speed25G)
+        | (PortFec::Rs, PortSpeed::Speed40G)
+        | (PortFec::Rs, PortSpeed::Speed50G) => Some(format!(
+            "Reed-Solomon FEC is not supported at {speed:?}; it requires \
+             100G or faster"
+        )),
+        (PortFec::Firecode, PortSpeed::Speed100G)
+        | (PortFec::Firecode, PortSpeed::Speed200G)
+        | (PortFec::Firecode, PortSpeed::Speed400G) => Some(format!(
+            "Firecode FEC is not supported at {speed:?}; use Reed-Solomon FEC"
+        )),
+        _ => None,
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rack_secret_threshold_defaults_to_majority() {
+        // Unset: majority = floor(n/2)+1.
+        assert_eq!(validate_rack_secret_threshold(None, 1).unwrap(), 1);
+        assert_eq!(validate_rack_secret_threshold(None, 3).unwrap(), 2);
+        assert_eq!(validate_rack_secret_threshold(None, 4).unwrap(), 3);
+        assert_eq!(validate_rack_secret_threshold(None, 5).unwrap(), 3);
+    }
+
+    #[test]
+    fn rack_secret_threshold_bounds() {
+        // Single-sled racks may only use a threshold of 1.
+        assert_eq!(validate_rack_secret_threshold(Some(1), 1).unwrap(), 1);
+        assert!(validate_rack_secret_threshold(Some(2), 1).is_err());
+
+        // Multi-sled racks require 2 <= k <= n.
+        assert!(validate_rack_secret_threshold(Some(1), 5).is_err());
+        assert_eq!(validate_rack_secret_threshold(Some(2), 5).unwrap(), 2);
+        assert_eq!(validate_rack_secret_threshold(Some(5), 5).unwrap(), 5);
+        assert!(validate_rack_secret_threshold(Some(6), 5).is_err());
+    }
+
+    #[test]
+    fn expected_zone_names_are_wildcard_and_bare_zone() {
+        assert_eq!(
+            expected_zone_dns_names("example.com"),
+            vec!["*.sys.example.com".to_string(), "example.com".to_string()],
+        );
+    }
+
+    #[test]
+    fn dns_name_matching_handles_wildcards_and_case() {
+        // Exact matches, case-insensitively.
+        assert!(dns_name_matches("example.com", "example.com"));
+        assert!(dns_name_matches("Example.COM", "example.com"));
+        assert!(!dns_name_matches("other.com", "example.com"));
+
+        // A wildcard matches exactly one left-most label.
+        assert!(dns_name_matches("*.sys.example.com", "recovery.sys.example.com"));
+        assert!(dns_name_matches("*.sys.example.com", "*.sys.example.com"));
+        assert!(!dns_name_matches("*.sys.example.com", "sys.example.com"));
+        assert!(!dns_name_matches(
+            "*.sys.example.com",
+            "a.b.sys.example.com"
+        ));
+        assert!(!dns_name_matches("*.sys.example.com", "example.com"));
+    }
+
+    #[test]
+    fn fec_speed_compatibility() {
+        use omicron_common::api::internal::shared::PortFec;
+        use omicron_common::api::internal::shared::PortSpeed;
+
+        // Reed-Solomon is only valid at 100G and above.
+        assert!(fec_speed_incompatibility(PortFec::Rs, PortSpeed::Speed25G)
+            .is_some());
+        assert!(fec_speed_incompatibility(PortFec::Rs, PortSpeed::Speed100G)
+            .is_none());
+
+        // Firecode is a low-rate scheme; it's invalid at 100G and above.
+        assert!(fec_speed_incompatibility(
+            PortFec::Firecode,
+            PortSpeed::Speed100G
+        )
+        .is_some());
+        assert!(fec_speed_incompatibility(
+            PortFec::Firecode,
+            PortSpeed::Speed25G
+        )
+        .is_none());
+
+        // `None` FEC is always permitted.
+        assert!(fec_speed_incompatibility(PortFec::None, PortSpeed::Speed400G)
+            .is_none());
+    }
+}